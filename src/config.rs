@@ -0,0 +1,123 @@
+//! Runtime configuration: serial port, baud rate, GCODE pattern, and axis→key
+//! bindings.
+//!
+//! Config is loaded from a TOML file at startup (default [`DEFAULT_CONFIG_PATH`])
+//! and can be viewed or reloaded at runtime from the Config mode reachable from the
+//! main UI. A missing file is created with defaults on first load.
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::injector::Key;
+use crate::macros::MacroRule;
+use crate::scancode::JogKey;
+use crate::VK_CONTROL;
+
+/// Default path the config is loaded from and saved to.
+pub(crate) const DEFAULT_CONFIG_PATH: &str = "pendant_watch.toml";
+
+/// Key bindings for each axis and direction of jog movement. Each binding is an
+/// ordered chord: keys are pressed down in order and released in reverse order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AxisBindings {
+    pub x_pos: Vec<Key>,
+    pub x_neg: Vec<Key>,
+    pub y_pos: Vec<Key>,
+    pub y_neg: Vec<Key>,
+    pub z_pos: Vec<Key>,
+    pub z_neg: Vec<Key>,
+}
+
+impl Default for AxisBindings {
+    /// Reproduces the original hardcoded Ctrl+arrow/page jog bindings.
+    fn default() -> Self {
+        let combo = |jog: JogKey| vec![Key::Virtual(VK_CONTROL), jog.virtual_key()];
+        Self {
+            x_pos: combo(JogKey::Right),
+            x_neg: combo(JogKey::Left),
+            y_pos: combo(JogKey::Up),
+            y_neg: combo(JogKey::Down),
+            z_pos: combo(JogKey::PageUp),
+            z_neg: combo(JogKey::PageDown),
+        }
+    }
+}
+
+impl AxisBindings {
+    /// Looks up the key chord bound to an axis letter ("X"/"Y"/"Z") and sign.
+    pub fn binding_for(&self, axis: &str, positive: bool) -> Option<&[Key]> {
+        let binding = match (axis, positive) {
+            ("X", true) => &self.x_pos,
+            ("X", false) => &self.x_neg,
+            ("Y", true) => &self.y_pos,
+            ("Y", false) => &self.y_neg,
+            ("Z", true) => &self.z_pos,
+            ("Z", false) => &self.z_neg,
+            _ => return None,
+        };
+        Some(binding)
+    }
+}
+
+/// User-editable application configuration, persisted as TOML.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    /// Serial port device name, e.g. "COM6" or "/dev/ttyUSB0".
+    pub port: String,
+    /// Serial baud rate.
+    pub baud_rate: u32,
+    /// Regex used to recognize G91G0 jog commands in Arrow and Mouse modes.
+    pub gcode_pattern: String,
+    /// Axis+direction to key-chord bindings used by Arrow mode.
+    pub axis_bindings: AxisBindings,
+    /// User-defined serial-line→key-action rules, checked against every incoming
+    /// line ahead of the mode-specific handling.
+    #[serde(default)]
+    pub macro_rules: Vec<MacroRule>,
+    /// Pixels of relative cursor movement injected per millimeter of GCODE axis
+    /// delta in Mouse mode.
+    #[serde(default = "default_mouse_sensitivity")]
+    pub mouse_sensitivity: f32,
+}
+
+/// Default pixels-per-mm sensitivity for Mouse mode cursor movement.
+fn default_mouse_sensitivity() -> f32 {
+    10.0
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            port: "COM6".to_string(),
+            baud_rate: 115200,
+            gcode_pattern: r"G91G0([XYZ])(-?\d+\.?\d*)".to_string(),
+            axis_bindings: AxisBindings::default(),
+            macro_rules: Vec::new(),
+            mouse_sensitivity: default_mouse_sensitivity(),
+        }
+    }
+}
+
+impl Config {
+    /// Loads configuration from `path`, writing out the defaults if the file
+    /// doesn't exist yet.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        if !path.exists() {
+            let config = Self::default();
+            config.save(path)?;
+            return Ok(config);
+        }
+        let contents = fs::read_to_string(path)?;
+        let config = toml::from_str(&contents)?;
+        Ok(config)
+    }
+
+    /// Persists this configuration to `path` as TOML.
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let contents = toml::to_string_pretty(self)?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+}
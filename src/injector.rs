@@ -0,0 +1,470 @@
+//! Keyboard and mouse injection backends.
+//!
+//! The rest of the crate talks to input devices through the [`KeyInjector`] and
+//! [`MouseInjector`] traits so that it never has to know whether it is driving
+//! the Windows `SendInput` API, X11's `XTEST` extension, or nothing at all (for
+//! dry runs and tests). Pick a concrete backend at startup based on the host
+//! platform.
+
+/// A key to inject, addressed either by platform virtual key code or by raw
+/// hardware scancode. Scancodes are honored by software that filters out
+/// synthetic virtual-key events (common in CAD and games).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value")]
+pub enum Key {
+    /// A platform virtual key code (e.g. Windows `VK_*`).
+    Virtual(u16),
+    /// A raw hardware scancode. `extended` marks keys in the 0xE0-prefixed
+    /// "extended" range (arrows, page up/down, etc.).
+    Scan { code: u16, extended: bool },
+}
+
+/// A mouse button, held down to modify drag behavior (e.g. CAD orbit/pan).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+/// Simulates mouse input: relative cursor motion and button state, used to drive
+/// CAD-style orbit/pan navigation from pendant jog commands.
+pub trait MouseInjector {
+    /// Moves the cursor by `(dx, dy)` pixels relative to its current position.
+    fn move_relative(&mut self, dx: i32, dy: i32);
+    /// Simulates a mouse button-down event.
+    fn button_down(&mut self, button: MouseButton);
+    /// Simulates a mouse button-up event.
+    fn button_up(&mut self, button: MouseButton);
+}
+
+/// Simulates keyboard input. Implementations own whatever platform handle they need
+/// (a window, a display connection, an event log) to turn keys into real or
+/// recorded key events.
+pub trait KeyInjector: MouseInjector {
+    /// Simulates a key-down event for the given key.
+    fn key_down(&mut self, key: Key);
+    /// Simulates a key-up event for the given key.
+    fn key_up(&mut self, key: Key);
+    /// Types out a string by simulating individual key presses and releases.
+    fn type_text(&mut self, text: &str);
+}
+
+/// Injects keyboard input via the Windows `SendInput` API.
+#[cfg(windows)]
+pub struct WindowsInjector;
+
+#[cfg(windows)]
+impl WindowsInjector {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[cfg(windows)]
+impl WindowsInjector {
+    /// Builds the `KEYBDINPUT` flags/fields for a key, per whether it is addressed
+    /// by virtual key code or by hardware scancode.
+    fn keybd_input(key: Key, key_up: bool) -> windows::Win32::UI::Input::KeyboardAndMouse::KEYBDINPUT {
+        use windows::Win32::UI::Input::KeyboardAndMouse::*;
+
+        let up_flag = if key_up { KEYEVENTF_KEYUP } else { KEYBD_EVENT_FLAGS(0) };
+        match key {
+            Key::Virtual(vk) => KEYBDINPUT {
+                wVk: VIRTUAL_KEY(vk),
+                wScan: 0,
+                dwFlags: up_flag,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+            Key::Scan { code, extended } => {
+                let mut flags = KEYEVENTF_SCANCODE | up_flag;
+                if extended {
+                    flags |= KEYEVENTF_EXTENDEDKEY;
+                }
+                KEYBDINPUT {
+                    wVk: VIRTUAL_KEY(0),
+                    wScan: code,
+                    dwFlags: flags,
+                    time: 0,
+                    dwExtraInfo: 0,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+impl WindowsInjector {
+    /// Maps a [`MouseButton`] to its `SendInput` down/up event flags.
+    fn mouse_button_flags(button: MouseButton, button_up: bool) -> windows::Win32::UI::Input::KeyboardAndMouse::MOUSE_EVENT_FLAGS {
+        use windows::Win32::UI::Input::KeyboardAndMouse::*;
+
+        match (button, button_up) {
+            (MouseButton::Left, false) => MOUSEEVENTF_LEFTDOWN,
+            (MouseButton::Left, true) => MOUSEEVENTF_LEFTUP,
+            (MouseButton::Middle, false) => MOUSEEVENTF_MIDDLEDOWN,
+            (MouseButton::Middle, true) => MOUSEEVENTF_MIDDLEUP,
+            (MouseButton::Right, false) => MOUSEEVENTF_RIGHTDOWN,
+            (MouseButton::Right, true) => MOUSEEVENTF_RIGHTUP,
+        }
+    }
+}
+
+#[cfg(windows)]
+impl MouseInjector for WindowsInjector {
+    /// # Safety
+    /// This function uses unsafe Windows API calls.
+    fn move_relative(&mut self, dx: i32, dy: i32) {
+        use windows::Win32::UI::Input::KeyboardAndMouse::*;
+
+        let input = INPUT {
+            r#type: INPUT_MOUSE,
+            Anonymous: INPUT_0 {
+                mi: MOUSEINPUT {
+                    dx,
+                    dy,
+                    mouseData: 0,
+                    dwFlags: MOUSEEVENTF_MOVE,
+                    time: 0,
+                    dwExtraInfo: 0,
+                },
+            },
+        };
+
+        unsafe {
+            SendInput(&[input], std::mem::size_of::<INPUT>() as i32);
+        }
+    }
+
+    /// # Safety
+    /// This function uses unsafe Windows API calls.
+    fn button_down(&mut self, button: MouseButton) {
+        use windows::Win32::UI::Input::KeyboardAndMouse::*;
+
+        let input = INPUT {
+            r#type: INPUT_MOUSE,
+            Anonymous: INPUT_0 {
+                mi: MOUSEINPUT {
+                    dx: 0,
+                    dy: 0,
+                    mouseData: 0,
+                    dwFlags: Self::mouse_button_flags(button, false),
+                    time: 0,
+                    dwExtraInfo: 0,
+                },
+            },
+        };
+
+        unsafe {
+            SendInput(&[input], std::mem::size_of::<INPUT>() as i32);
+        }
+    }
+
+    /// # Safety
+    /// This function uses unsafe Windows API calls.
+    fn button_up(&mut self, button: MouseButton) {
+        use windows::Win32::UI::Input::KeyboardAndMouse::*;
+
+        let input = INPUT {
+            r#type: INPUT_MOUSE,
+            Anonymous: INPUT_0 {
+                mi: MOUSEINPUT {
+                    dx: 0,
+                    dy: 0,
+                    mouseData: 0,
+                    dwFlags: Self::mouse_button_flags(button, true),
+                    time: 0,
+                    dwExtraInfo: 0,
+                },
+            },
+        };
+
+        unsafe {
+            SendInput(&[input], std::mem::size_of::<INPUT>() as i32);
+        }
+    }
+}
+
+#[cfg(windows)]
+impl KeyInjector for WindowsInjector {
+    /// # Safety
+    /// This function uses unsafe Windows API calls.
+    fn key_down(&mut self, key: Key) {
+        use windows::Win32::UI::Input::KeyboardAndMouse::*;
+
+        let input = INPUT {
+            r#type: INPUT_KEYBOARD,
+            Anonymous: INPUT_0 {
+                ki: Self::keybd_input(key, false),
+            },
+        };
+
+        unsafe {
+            SendInput(&[input], std::mem::size_of::<INPUT>() as i32);
+        }
+    }
+
+    /// # Safety
+    /// This function uses unsafe Windows API calls.
+    fn key_up(&mut self, key: Key) {
+        use windows::Win32::UI::Input::KeyboardAndMouse::*;
+
+        let input = INPUT {
+            r#type: INPUT_KEYBOARD,
+            Anonymous: INPUT_0 {
+                ki: Self::keybd_input(key, true),
+            },
+        };
+
+        unsafe {
+            SendInput(&[input], std::mem::size_of::<INPUT>() as i32);
+        }
+    }
+
+    /// # Safety
+    /// This function uses unsafe Windows API calls for each character.
+    fn type_text(&mut self, text: &str) {
+        use windows::Win32::UI::Input::KeyboardAndMouse::*;
+
+        for ch in text.chars() {
+            let input_down = INPUT {
+                r#type: INPUT_KEYBOARD,
+                Anonymous: INPUT_0 {
+                    ki: KEYBDINPUT {
+                        wVk: VIRTUAL_KEY(0),
+                        wScan: ch as u16,
+                        dwFlags: KEYEVENTF_UNICODE,
+                        time: 0,
+                        dwExtraInfo: 0,
+                    },
+                },
+            };
+            unsafe {
+                SendInput(&[input_down], std::mem::size_of::<INPUT>() as i32);
+            }
+
+            let input_up = INPUT {
+                r#type: INPUT_KEYBOARD,
+                Anonymous: INPUT_0 {
+                    ki: KEYBDINPUT {
+                        wVk: VIRTUAL_KEY(0),
+                        wScan: ch as u16,
+                        dwFlags: KEYEVENTF_KEYUP | KEYEVENTF_UNICODE,
+                        time: 0,
+                        dwExtraInfo: 0,
+                    },
+                },
+            };
+            unsafe {
+                SendInput(&[input_up], std::mem::size_of::<INPUT>() as i32);
+            }
+        }
+    }
+}
+
+/// Injects keyboard input on Linux via X11's `XTEST` extension, which lets a client
+/// post synthetic core input events without owning a window.
+#[cfg(unix)]
+pub struct XcbInjector {
+    conn: xcb::Connection,
+}
+
+#[cfg(unix)]
+impl XcbInjector {
+    /// Connects to the X server and checks that the `XTEST` extension is available.
+    pub fn new() -> xcb::Result<Self> {
+        let (conn, _screen_num) = xcb::Connection::connect(None)?;
+        conn.wait_for_reply(conn.send_request(&xcb::xtest::GetVersion {
+            major_version: 2,
+            minor_version: 2,
+        }))?;
+        Ok(Self { conn })
+    }
+
+    /// Sends a single `XTEST` fake input event; `event_type` is
+    /// `xcb::x::KEY_PRESS` or `xcb::x::KEY_RELEASE`.
+    fn fake_key(&self, event_type: u32, keycode: u8) {
+        self.conn.send_request(&xcb::xtest::FakeInput {
+            r#type: event_type as u8,
+            detail: keycode,
+            time: xcb::x::CURRENT_TIME,
+            root: xcb::x::WINDOW_NONE,
+            root_x: 0,
+            root_y: 0,
+            deviceid: 0,
+        });
+    }
+
+    /// Looks up the keycode bound to `keysym` in the server's current keyboard
+    /// mapping, if any.
+    fn keycode_for_keysym(&self, keysym: u32) -> Option<u8> {
+        let setup = self.conn.get_setup();
+        let min_keycode = setup.min_keycode();
+        let max_keycode = setup.max_keycode();
+        let count = max_keycode - min_keycode + 1;
+
+        let cookie = self.conn.send_request(&xcb::x::GetKeyboardMapping {
+            first_keycode: min_keycode,
+            count,
+        });
+        let reply = self.conn.wait_for_reply(cookie).ok()?;
+        let per_keycode = reply.keysyms_per_keycode() as usize;
+
+        reply
+            .keysyms()
+            .chunks(per_keycode)
+            .position(|syms| syms.contains(&keysym))
+            .map(|index| min_keycode + index as u8)
+    }
+
+    /// Resolves a [`Key`] to an X11 keycode. Virtual keys are resolved via the
+    /// keysym mapping; raw scancodes are translated using the common evdev
+    /// convention of `keycode = scancode + 8` (XTEST has no separate notion of
+    /// scancode sets, so `Key::Scan` is always treated as Set 1).
+    fn keycode_for_key(&self, key: Key) -> Option<u8> {
+        match key {
+            Key::Virtual(vk) => self.keycode_for_keysym(vk as u32),
+            Key::Scan { code, .. } => Some((code as u8).wrapping_add(8)),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl XcbInjector {
+    /// Sends a single `XTEST` fake mouse-button event.
+    fn fake_button(&self, press: bool, detail: u8) {
+        let event_type = if press { xcb::x::BUTTON_PRESS } else { xcb::x::BUTTON_RELEASE };
+        self.conn.send_request(&xcb::xtest::FakeInput {
+            r#type: event_type as u8,
+            detail,
+            time: xcb::x::CURRENT_TIME,
+            root: xcb::x::WINDOW_NONE,
+            root_x: 0,
+            root_y: 0,
+            deviceid: 0,
+        });
+    }
+
+    /// Sends a single `XTEST` fake pointer-motion event. `detail: 1` marks the
+    /// motion as relative to the cursor's current position, rather than an
+    /// absolute screen coordinate.
+    fn fake_motion(&self, dx: i32, dy: i32) {
+        self.conn.send_request(&xcb::xtest::FakeInput {
+            r#type: xcb::x::MOTION_NOTIFY as u8,
+            detail: 1,
+            time: xcb::x::CURRENT_TIME,
+            root: xcb::x::WINDOW_NONE,
+            root_x: dx as i16,
+            root_y: dy as i16,
+            deviceid: 0,
+        });
+    }
+
+    /// Maps a [`MouseButton`] to its X11 pointer button code.
+    fn button_code(button: MouseButton) -> u8 {
+        match button {
+            MouseButton::Left => 1,
+            MouseButton::Middle => 2,
+            MouseButton::Right => 3,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl MouseInjector for XcbInjector {
+    fn move_relative(&mut self, dx: i32, dy: i32) {
+        self.fake_motion(dx, dy);
+        self.conn.flush().ok();
+    }
+
+    fn button_down(&mut self, button: MouseButton) {
+        self.fake_button(true, Self::button_code(button));
+        self.conn.flush().ok();
+    }
+
+    fn button_up(&mut self, button: MouseButton) {
+        self.fake_button(false, Self::button_code(button));
+        self.conn.flush().ok();
+    }
+}
+
+#[cfg(unix)]
+impl KeyInjector for XcbInjector {
+    fn key_down(&mut self, key: Key) {
+        if let Some(keycode) = self.keycode_for_key(key) {
+            self.fake_key(xcb::x::KEY_PRESS, keycode);
+            self.conn.flush().ok();
+        }
+    }
+
+    fn key_up(&mut self, key: Key) {
+        if let Some(keycode) = self.keycode_for_key(key) {
+            self.fake_key(xcb::x::KEY_RELEASE, keycode);
+            self.conn.flush().ok();
+        }
+    }
+
+    fn type_text(&mut self, text: &str) {
+        for ch in text.chars() {
+            let keysym = ch as u32;
+            if let Some(keycode) = self.keycode_for_keysym(keysym) {
+                self.fake_key(xcb::x::KEY_PRESS, keycode);
+                self.fake_key(xcb::x::KEY_RELEASE, keycode);
+            }
+        }
+        self.conn.flush().ok();
+    }
+}
+
+/// Records injected events instead of sending them anywhere. Used for dry-run mode
+/// and for exercising `serial_to_arrow`/`serial_to_gcode` without a real keyboard.
+#[derive(Default)]
+pub struct HeadlessInjector {
+    pub events: Vec<HeadlessEvent>,
+}
+
+/// A single recorded event from a [`HeadlessInjector`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeadlessEvent {
+    KeyDown(Key),
+    KeyUp(Key),
+    Text(String),
+    MouseMove(i32, i32),
+    MouseButtonDown(MouseButton),
+    MouseButtonUp(MouseButton),
+}
+
+impl HeadlessInjector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl MouseInjector for HeadlessInjector {
+    fn move_relative(&mut self, dx: i32, dy: i32) {
+        self.events.push(HeadlessEvent::MouseMove(dx, dy));
+    }
+
+    fn button_down(&mut self, button: MouseButton) {
+        self.events.push(HeadlessEvent::MouseButtonDown(button));
+    }
+
+    fn button_up(&mut self, button: MouseButton) {
+        self.events.push(HeadlessEvent::MouseButtonUp(button));
+    }
+}
+
+impl KeyInjector for HeadlessInjector {
+    fn key_down(&mut self, key: Key) {
+        self.events.push(HeadlessEvent::KeyDown(key));
+    }
+
+    fn key_up(&mut self, key: Key) {
+        self.events.push(HeadlessEvent::KeyUp(key));
+    }
+
+    fn type_text(&mut self, text: &str) {
+        self.events.push(HeadlessEvent::Text(text.to_string()));
+    }
+}
@@ -0,0 +1,267 @@
+//! Cursor-addressable line editor for composing GCODE commands interactively.
+
+use std::collections::VecDeque;
+
+/// Maximum number of prior commands retained in history.
+const HISTORY_CAPACITY: usize = 50;
+
+/// An editable line of text with an independent cursor position, supporting
+/// mid-string insert/delete and cursor navigation.
+#[derive(Debug, Clone, Default)]
+pub struct Buffer {
+    chars: Vec<char>,
+    cursor: usize,
+}
+
+impl Buffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current contents as a string.
+    pub fn as_str(&self) -> String {
+        self.chars.iter().collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    /// Splits the rendered line into the parts before and after the cursor, so
+    /// callers can draw a cursor marker between them.
+    pub fn split_at_cursor(&self) -> (String, String) {
+        (
+            self.chars[..self.cursor].iter().collect(),
+            self.chars[self.cursor..].iter().collect(),
+        )
+    }
+
+    /// Inserts a character at the cursor and advances it.
+    pub fn insert(&mut self, c: char) {
+        self.chars.insert(self.cursor, c);
+        self.cursor += 1;
+    }
+
+    /// Deletes the character before the cursor (Backspace). Returns `true` if a
+    /// character was removed.
+    pub fn delete_before(&mut self) -> bool {
+        if self.cursor == 0 {
+            return false;
+        }
+        self.cursor -= 1;
+        self.chars.remove(self.cursor);
+        true
+    }
+
+    /// Moves the cursor one position left, stopping at the start.
+    pub fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    /// Moves the cursor one position right, stopping at the end.
+    pub fn move_right(&mut self) {
+        if self.cursor < self.chars.len() {
+            self.cursor += 1;
+        }
+    }
+
+    /// Moves the cursor to the start of the buffer, returning its previous position.
+    pub fn move_home(&mut self) -> usize {
+        let previous = self.cursor;
+        self.cursor = 0;
+        previous
+    }
+
+    /// Moves the cursor to the end of the buffer, returning its previous position.
+    pub fn move_end(&mut self) -> usize {
+        let previous = self.cursor;
+        self.cursor = self.chars.len();
+        previous
+    }
+
+    /// Replaces the contents with `text`, placing the cursor at the end.
+    pub fn set(&mut self, text: &str) {
+        self.chars = text.chars().collect();
+        self.cursor = self.chars.len();
+    }
+
+    /// Clears the buffer.
+    pub fn clear(&mut self) {
+        self.chars.clear();
+        self.cursor = 0;
+    }
+}
+
+impl std::fmt::Display for Buffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A fixed-capacity ring of previously submitted commands, walked with Up/Down.
+pub struct History {
+    entries: VecDeque<String>,
+    /// Entry the user is currently browsing, if any. `None` means the user is
+    /// editing a fresh, not-yet-submitted line.
+    cursor: Option<usize>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self {
+            entries: VecDeque::with_capacity(HISTORY_CAPACITY),
+            cursor: None,
+        }
+    }
+
+    /// Records a submitted command, dropping the oldest entry if at capacity.
+    pub fn push(&mut self, command: String) {
+        if self.entries.len() == HISTORY_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(command);
+        self.cursor = None;
+    }
+
+    /// Walks one entry further into the past.
+    pub fn prev(&mut self) -> Option<&str> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let next_cursor = match self.cursor {
+            None => self.entries.len() - 1,
+            Some(0) => 0,
+            Some(i) => i - 1,
+        };
+        self.cursor = Some(next_cursor);
+        self.entries.get(next_cursor).map(String::as_str)
+    }
+
+    /// Walks one entry back toward the present, returning `Some("")` once the
+    /// user walks past the most recent entry, back to a fresh line.
+    pub fn next(&mut self) -> Option<&str> {
+        match self.cursor {
+            None => None,
+            Some(i) if i + 1 < self.entries.len() => {
+                self.cursor = Some(i + 1);
+                self.entries.get(i + 1).map(String::as_str)
+            }
+            Some(_) => {
+                self.cursor = None;
+                Some("")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_delete_before_move_the_cursor() {
+        let mut buf = Buffer::new();
+        buf.insert('a');
+        buf.insert('b');
+        buf.insert('c');
+        assert_eq!(buf.as_str(), "abc");
+        assert!(buf.delete_before());
+        assert_eq!(buf.as_str(), "ab");
+        buf.move_left();
+        buf.insert('X');
+        assert_eq!(buf.as_str(), "aXb");
+    }
+
+    #[test]
+    fn delete_before_at_start_of_buffer_is_a_no_op() {
+        let mut buf = Buffer::new();
+        buf.insert('a');
+        buf.move_home();
+        assert!(!buf.delete_before());
+        assert_eq!(buf.as_str(), "a");
+    }
+
+    #[test]
+    fn move_left_and_right_stop_at_the_buffer_edges() {
+        let mut buf = Buffer::new();
+        buf.set("ab");
+        buf.move_home();
+        buf.move_left();
+        buf.move_left();
+        assert_eq!(buf.split_at_cursor(), ("".to_string(), "ab".to_string()));
+        buf.move_right();
+        buf.move_right();
+        buf.move_right();
+        assert_eq!(buf.split_at_cursor(), ("ab".to_string(), "".to_string()));
+    }
+
+    #[test]
+    fn move_home_and_end_return_the_previous_cursor_position() {
+        let mut buf = Buffer::new();
+        buf.set("abcd");
+        buf.move_left();
+        buf.move_left();
+        assert_eq!(buf.move_home(), 2);
+        assert_eq!(buf.move_end(), 0);
+        assert_eq!(buf.split_at_cursor(), ("abcd".to_string(), "".to_string()));
+    }
+
+    #[test]
+    fn set_replaces_contents_and_places_cursor_at_the_end() {
+        let mut buf = Buffer::new();
+        buf.insert('x');
+        buf.set("hello");
+        assert_eq!(buf.as_str(), "hello");
+        assert_eq!(buf.split_at_cursor(), ("hello".to_string(), "".to_string()));
+    }
+
+    #[test]
+    fn clear_empties_the_buffer_and_resets_the_cursor() {
+        let mut buf = Buffer::new();
+        buf.set("hello");
+        buf.clear();
+        assert!(buf.is_empty());
+        assert_eq!(buf.split_at_cursor(), ("".to_string(), "".to_string()));
+    }
+
+    #[test]
+    fn history_prev_walks_from_most_recent_to_oldest() {
+        let mut history = History::new();
+        history.push("G1".to_string());
+        history.push("G2".to_string());
+        history.push("G3".to_string());
+        assert_eq!(history.prev(), Some("G3"));
+        assert_eq!(history.prev(), Some("G2"));
+        assert_eq!(history.prev(), Some("G1"));
+        // Stops at the oldest entry rather than wrapping.
+        assert_eq!(history.prev(), Some("G1"));
+    }
+
+    #[test]
+    fn history_next_returns_to_a_fresh_line_past_the_most_recent_entry() {
+        let mut history = History::new();
+        history.push("G1".to_string());
+        history.push("G2".to_string());
+        history.prev();
+        history.prev();
+        assert_eq!(history.next(), Some("G2"));
+        assert_eq!(history.next(), Some(""));
+        // Once back at a fresh line, `next` has nothing further to walk to.
+        assert_eq!(history.next(), None);
+    }
+
+    #[test]
+    fn history_push_drops_the_oldest_entry_once_at_capacity() {
+        let mut history = History::new();
+        for i in 0..HISTORY_CAPACITY + 1 {
+            history.push(format!("G{i}"));
+        }
+        let newest = format!("G{HISTORY_CAPACITY}");
+        assert_eq!(history.prev(), Some(newest.as_str()));
+        // Walking all the way back should stop at "G1", since "G0" was evicted.
+        for _ in 0..HISTORY_CAPACITY {
+            history.prev();
+        }
+        assert_eq!(history.prev(), Some("G1"));
+    }
+}
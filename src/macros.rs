@@ -0,0 +1,185 @@
+//! User-defined macro engine: regex-matched serial lines dispatched to ordered
+//! keyboard action sequences.
+//!
+//! Rules are configured in TOML (see [`MacroRule`]) and compiled once into a
+//! [`MacroEngine`] at load/reload time, mirroring how `AppState::gcode_regex` is
+//! compiled once from `Config::gcode_pattern`. `run_event_loop` asks the engine
+//! for the first matching rule and runs its actions through the `KeyInjector`,
+//! letting a pendant command fire an arbitrary multi-key chord or sequence
+//! instead of only the hardcoded `G91G0` jog.
+
+use std::time::Duration;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::injector::{Key, KeyInjector};
+
+/// Upper bound on a single `DelayMs` action. `MacroEngine::dispatch` runs
+/// synchronously on the main loop's queue-drain step (see `run_event_loop`),
+/// so an unbounded delay would freeze keyboard-input polling and status-bar
+/// redraws for its duration — exactly the kind of stall the chunk0-6 reader
+/// thread/queue was introduced to eliminate for serial bursts.
+const MAX_DELAY_MS: u64 = 200;
+
+/// A single step in a macro's action sequence.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MacroAction {
+    /// Presses a key down without releasing it.
+    KeyDown(Key),
+    /// Releases a previously pressed key.
+    KeyUp(Key),
+    /// Presses and immediately releases a key.
+    Press(Key),
+    /// Types out literal text, one key event per character.
+    TypeText(String),
+    /// Pauses for the given number of milliseconds (clamped to
+    /// [`MAX_DELAY_MS`]) before the next action.
+    DelayMs(u64),
+}
+
+impl MacroAction {
+    /// Runs this action through `injector`.
+    fn run(&self, injector: &mut dyn KeyInjector) {
+        match self {
+            MacroAction::KeyDown(key) => injector.key_down(*key),
+            MacroAction::KeyUp(key) => injector.key_up(*key),
+            MacroAction::Press(key) => {
+                injector.key_down(*key);
+                injector.key_up(*key);
+            }
+            MacroAction::TypeText(text) => injector.type_text(text),
+            MacroAction::DelayMs(ms) => std::thread::sleep(Duration::from_millis((*ms).min(MAX_DELAY_MS))),
+        }
+    }
+}
+
+/// A user-defined rule: incoming serial lines matching `pattern` trigger
+/// `actions` in order. Stored in [`Config`](crate::config::Config) and compiled
+/// once into a [`MacroEngine`] at load/reload time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MacroRule {
+    /// Regex matched against each incoming serial line.
+    pub pattern: String,
+    /// Actions run in order when `pattern` matches.
+    pub actions: Vec<MacroAction>,
+}
+
+/// Compiled macro rules, recompiled whenever the config is (re)loaded.
+pub struct MacroEngine {
+    rules: Vec<(Regex, Vec<MacroAction>)>,
+}
+
+impl MacroEngine {
+    /// Compiles each rule's pattern once. Fails on the first invalid pattern.
+    pub fn compile(rules: &[MacroRule]) -> Result<Self, regex::Error> {
+        let rules = rules
+            .iter()
+            .map(|rule| Ok((Regex::new(&rule.pattern)?, rule.actions.clone())))
+            .collect::<Result<Vec<_>, regex::Error>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Runs the actions of the first rule whose pattern matches `line`. Returns
+    /// `true` if a rule matched and its actions were dispatched.
+    pub fn dispatch(&self, line: &str, injector: &mut dyn KeyInjector) -> bool {
+        let Some((_, actions)) = self.rules.iter().find(|(re, _)| re.is_match(line)) else {
+            return false;
+        };
+        for action in actions {
+            action.run(injector);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::injector::{HeadlessEvent, HeadlessInjector};
+
+    #[test]
+    fn dispatch_runs_the_actions_of_the_first_matching_rule() {
+        let rules = vec![
+            MacroRule {
+                pattern: "^G28$".to_string(),
+                actions: vec![MacroAction::Press(Key::Virtual(1))],
+            },
+            MacroRule {
+                pattern: "^G\\d+$".to_string(),
+                actions: vec![MacroAction::Press(Key::Virtual(2))],
+            },
+        ];
+        let engine = MacroEngine::compile(&rules).unwrap();
+        let mut injector = HeadlessInjector::new();
+
+        assert!(engine.dispatch("G28", &mut injector));
+        assert_eq!(
+            injector.events,
+            vec![
+                HeadlessEvent::KeyDown(Key::Virtual(1)),
+                HeadlessEvent::KeyUp(Key::Virtual(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn dispatch_returns_false_when_no_rule_matches() {
+        let rules = vec![MacroRule {
+            pattern: "^G28$".to_string(),
+            actions: vec![MacroAction::Press(Key::Virtual(1))],
+        }];
+        let engine = MacroEngine::compile(&rules).unwrap();
+        let mut injector = HeadlessInjector::new();
+
+        assert!(!engine.dispatch("M114", &mut injector));
+        assert!(injector.events.is_empty());
+    }
+
+    #[test]
+    fn key_down_up_and_type_text_actions_are_forwarded_in_order() {
+        let rules = vec![MacroRule {
+            pattern: "^shift-a$".to_string(),
+            actions: vec![
+                MacroAction::KeyDown(Key::Virtual(16)),
+                MacroAction::TypeText("a".to_string()),
+                MacroAction::KeyUp(Key::Virtual(16)),
+            ],
+        }];
+        let engine = MacroEngine::compile(&rules).unwrap();
+        let mut injector = HeadlessInjector::new();
+
+        assert!(engine.dispatch("shift-a", &mut injector));
+        assert_eq!(
+            injector.events,
+            vec![
+                HeadlessEvent::KeyDown(Key::Virtual(16)),
+                HeadlessEvent::Text("a".to_string()),
+                HeadlessEvent::KeyUp(Key::Virtual(16)),
+            ]
+        );
+    }
+
+    #[test]
+    fn delay_ms_action_is_clamped_to_max_delay_ms() {
+        let rules = vec![MacroRule {
+            pattern: "^G4$".to_string(),
+            actions: vec![MacroAction::DelayMs(MAX_DELAY_MS * 10)],
+        }];
+        let engine = MacroEngine::compile(&rules).unwrap();
+        let mut injector = HeadlessInjector::new();
+
+        let start = std::time::Instant::now();
+        assert!(engine.dispatch("G4", &mut injector));
+        assert!(start.elapsed() < Duration::from_millis(MAX_DELAY_MS * 2));
+    }
+
+    #[test]
+    fn compile_rejects_an_invalid_pattern() {
+        let rules = vec![MacroRule {
+            pattern: "(".to_string(),
+            actions: vec![],
+        }];
+        assert!(MacroEngine::compile(&rules).is_err());
+    }
+}
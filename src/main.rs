@@ -8,6 +8,7 @@
 //!
 //! - **Arrow Mode**: Translates GCODE G91G0 commands into arrow key presses with Ctrl modifier
 //! - **Gcode Mode**: Allows manual typing and sending of GCODE commands to the device
+//! - **Mouse Mode**: Translates GCODE axis moves into relative cursor motion for CAD orbit/pan
 //! - Real-time serial communication with configurable port and baud rate
 //! - Terminal-based UI with status display
 //!
@@ -16,18 +17,46 @@
 //! Run the application and use:
 //! - '1' to switch to Arrow Mode
 //! - '2' to switch to Gcode Mode
+//! - '3' to switch to Config Mode
+//! - '4' to switch to Mouse Mode
 //! - 'q' to quit
 //!
 //! In Gcode Mode, type commands and press Enter to send them.
+//! In Mouse Mode, press 'p' to toggle between orbit (held middle button) and
+//! pan (held Shift+middle button).
+
+mod config;
+mod injector;
+mod line_editor;
+mod macros;
+mod queue;
+mod scancode;
 
 use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
 use std::time::{Duration, Instant};
 use regex::Regex;
-use windows::Win32::UI::Input::KeyboardAndMouse::*;
 use crossterm::{
     event::{self, Event, KeyCode},
     terminal::{disable_raw_mode, enable_raw_mode},
 };
+use config::Config;
+use injector::{Key, KeyInjector, MouseButton};
+use line_editor::{Buffer, History};
+use macros::MacroEngine;
+use queue::{LineQueue, SharedLineQueue};
+use scancode::{InjectionMode, ScancodeSet};
+
+/// Maximum number of unprocessed serial lines buffered between the reader
+/// thread and the main loop before the oldest is dropped.
+const QUEUE_CAPACITY: usize = 64;
+
+/// How long Mouse mode waits after the last jog move before releasing a held
+/// drag button, so a pendant going quiet mid-orbit doesn't leave the button
+/// stuck down.
+const MOUSE_DRAG_IDLE_TIMEOUT: Duration = Duration::from_millis(250);
 
 /// Operating modes for the pendant controller
 #[derive(PartialEq)]
@@ -36,6 +65,11 @@ enum Mode {
     Arrow,
     /// Gcode mode: Allows manual GCODE input and transmission
     Gcode,
+    /// Config mode: Views the loaded configuration and reloads it from disk
+    Config,
+    /// Mouse mode: Translates GCODE axis moves into relative cursor motion for
+    /// CAD-style orbit/pan navigation
+    Mouse,
 }
 
 /// Application state containing current mode, connection status, and command history
@@ -44,19 +78,86 @@ struct AppState {
     connected: bool,
     last_command: Option<String>,
     last_command_time: Option<Instant>,
-    gcode_input: String,
+    /// Line being composed in Gcode mode, with cursor position
+    gcode_input: Buffer,
+    /// Previously submitted Gcode mode commands, walked with Up/Down
+    gcode_history: History,
+    /// Whether jog keys are injected as virtual keys or raw hardware scancodes
+    injection_mode: InjectionMode,
+    /// Scancode set used to translate jog keys when in scancode injection mode
+    scancode_set: ScancodeSet,
+    /// Loaded configuration (port, baud, GCODE pattern, axis bindings)
+    config: Config,
+    /// Path `config` was loaded from and is saved back to
+    config_path: PathBuf,
+    /// `config.gcode_pattern` compiled once at load/reload time
+    gcode_regex: Regex,
+    /// `config.macro_rules` compiled once at load/reload time
+    macro_engine: MacroEngine,
+    /// Number of serial lines waiting in the reader-thread queue as of the last drain
+    queue_depth: usize,
+    /// Whether Mouse mode holds Shift+middle button (pan) instead of plain
+    /// middle button (orbit) while injecting cursor movement
+    mouse_pan: bool,
+    /// Whether the Mouse-mode drag button is currently held, and with which
+    /// modifier: `Some(true)` for Shift+Middle (pan), `Some(false)` for plain
+    /// Middle (orbit), `None` if released
+    mouse_drag_active: Option<bool>,
+    /// Time of the last Mouse-mode cursor move, used to release a held drag
+    /// once jog input goes idle
+    mouse_last_move: Option<Instant>,
+}
+
+/// Compiles `pattern` and checks it has the axis/value capture groups
+/// `serial_to_arrow`/`serial_to_mouse` index unconditionally (`captures[1]`,
+/// `captures[2]`), so a user-edited `gcode_pattern` with too few groups is
+/// rejected here instead of panicking the first time a serial line matches it.
+fn compile_gcode_regex(pattern: &str) -> Result<Regex, Box<dyn std::error::Error>> {
+    let regex = Regex::new(pattern)?;
+    // `captures_len()` includes the implicit group 0 (the whole match).
+    if regex.captures_len() < 3 {
+        return Err(format!(
+            "gcode_pattern {:?} must have at least 2 capture groups (axis, value), found {}",
+            pattern,
+            regex.captures_len() - 1
+        )
+        .into());
+    }
+    Ok(regex)
 }
 
 impl AppState {
-    /// Creates a new application state with default values
-    fn new() -> Self {
-        Self {
+    /// Creates a new application state from a loaded configuration
+    fn new(config: Config, config_path: PathBuf) -> Result<Self, Box<dyn std::error::Error>> {
+        let gcode_regex = compile_gcode_regex(&config.gcode_pattern)?;
+        let macro_engine = MacroEngine::compile(&config.macro_rules)?;
+        Ok(Self {
             mode: Mode::Gcode,
             connected: false,
             last_command: None,
             last_command_time: None,
-            gcode_input: String::new(),
-        }
+            gcode_input: Buffer::new(),
+            gcode_history: History::new(),
+            injection_mode: InjectionMode::Virtual,
+            scancode_set: ScancodeSet::Set1,
+            config,
+            config_path,
+            gcode_regex,
+            macro_engine,
+            queue_depth: 0,
+            mouse_pan: false,
+            mouse_drag_active: None,
+            mouse_last_move: None,
+        })
+    }
+
+    /// Reloads `config` from `config_path`, recompiling the GCODE regex and macro rules.
+    fn reload_config(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let config = Config::load(&self.config_path)?;
+        self.gcode_regex = compile_gcode_regex(&config.gcode_pattern)?;
+        self.macro_engine = MacroEngine::compile(&config.macro_rules)?;
+        self.config = config;
+        Ok(())
     }
 
     /// Updates the last command and its timestamp
@@ -71,89 +172,6 @@ impl AppState {
     }
 }
 
-/// Simulates a key press down event using Windows API
-/// # Safety
-/// This function uses unsafe Windows API calls
-fn send_key_down(key_code: u16) {
-    let input = INPUT {
-        r#type: INPUT_KEYBOARD,
-        Anonymous: INPUT_0 {
-            ki: KEYBDINPUT {
-                wVk: VIRTUAL_KEY(key_code),
-                wScan: 0,
-                dwFlags: KEYBD_EVENT_FLAGS(0),
-                time: 0,
-                dwExtraInfo: 0,
-            }
-        }
-    };
-
-    unsafe {
-        SendInput(&[input], std::mem::size_of::<INPUT>() as i32);
-    }
-}
-
-/// Simulates a key release event using Windows API
-/// # Safety
-/// This function uses unsafe Windows API calls
-fn send_key_up(key_code: u16) {
-    let input = INPUT {
-        r#type: INPUT_KEYBOARD,
-        Anonymous: INPUT_0 {
-            ki: KEYBDINPUT {
-                wVk: VIRTUAL_KEY(key_code),
-                wScan: 0,
-                dwFlags: KEYEVENTF_KEYUP,
-                time: 0,
-                dwExtraInfo: 0,
-            }
-        }
-    };
-
-    unsafe {
-        SendInput(&[input], std::mem::size_of::<INPUT>() as i32);
-    }
-}
-
-/// Types out text by simulating individual key presses and releases
-/// # Safety
-/// This function uses unsafe Windows API calls for each character
-fn type_text(text: &str) {
-    for ch in text.chars() {
-        let input_down = INPUT {
-            r#type: INPUT_KEYBOARD,
-            Anonymous: INPUT_0 {
-                ki: KEYBDINPUT {
-                    wVk: VIRTUAL_KEY(0),
-                    wScan: ch as u16,
-                    dwFlags: KEYEVENTF_UNICODE,
-                    time: 0,
-                    dwExtraInfo: 0,
-                }
-            }
-        };
-        unsafe {
-            SendInput(&[input_down], std::mem::size_of::<INPUT>() as i32);
-        }
-
-        let input_up = INPUT {
-            r#type: INPUT_KEYBOARD,
-            Anonymous: INPUT_0 {
-                ki: KEYBDINPUT {
-                    wVk: VIRTUAL_KEY(0),
-                    wScan: ch as u16,
-                    dwFlags: KEYEVENTF_KEYUP | KEYEVENTF_UNICODE,
-                    time: 0,
-                    dwExtraInfo: 0,
-                }
-            }
-        };
-        unsafe {
-            SendInput(&[input_up], std::mem::size_of::<INPUT>() as i32);
-        }
-    }
-}
-
 /// Draws the terminal-based status bar and instructions
 fn draw_status_bar(state: &AppState) -> Result<(), Box<dyn std::error::Error>> {
     // Clear screen and move cursor to top-left
@@ -190,10 +208,15 @@ fn draw_status_bar(state: &AppState) -> Result<(), Box<dyn std::error::Error>> {
         print!("Time: N/A │ ");
     }
 
+    // Display number of serial lines waiting to be processed
+    print!("Queue: {} │ ", state.queue_depth);
+
     // Display current mode
     match state.mode {
         Mode::Arrow => println!("Mode: Arrow │"),
         Mode::Gcode => println!("Mode: Gcode │"),
+        Mode::Config => println!("Mode: Config │"),
+        Mode::Mouse => println!("Mode: Mouse │"),
     }
 
     // Close status bar border
@@ -204,12 +227,39 @@ fn draw_status_bar(state: &AppState) -> Result<(), Box<dyn std::error::Error>> {
     match state.mode {
         Mode::Arrow => {
             println!("Arrow Mode: Receiving commands from device and simulating keyboard presses.");
-            println!("Press '1' for Arrow Mode, '2' for Gcode Mode, 'q' to quit.");
+            let injection_mode = match state.injection_mode {
+                InjectionMode::Virtual => "Virtual Key".to_string(),
+                InjectionMode::Scancode => format!(
+                    "Scancode ({})",
+                    match state.scancode_set {
+                        ScancodeSet::Set1 => "Set 1",
+                        ScancodeSet::Set2 => "Set 2",
+                    }
+                ),
+            };
+            println!("Injection: {} │ Press 'm' to toggle, 'e' to change scancode set.", injection_mode);
+            println!("Press '1' for Arrow Mode, '2' for Gcode Mode, '4' for Mouse Mode, 'q' to quit.");
         }
         Mode::Gcode => {
             println!("Gcode Mode: Type GCODE commands and press Enter to send to device.");
-            println!("Current input: {}", state.gcode_input);
-            println!("Press '1' for Arrow Mode, '2' for Gcode Mode, 'q' to quit.");
+            let (before, after) = state.gcode_input.split_at_cursor();
+            println!("Current input: {}|{}", before, after);
+            println!("Press '1' for Arrow Mode, '2' for Gcode Mode, '4' for Mouse Mode, 'q' to quit.");
+        }
+        Mode::Config => {
+            println!("Config Mode: {}", state.config_path.display());
+            println!("Port: {} │ Baud: {}", state.config.port, state.config.baud_rate);
+            println!("GCODE pattern: {}", state.config.gcode_pattern);
+            println!("Press 'r' to reload from disk, '1' for Arrow Mode, '2' for Gcode Mode, 'q' to quit.");
+        }
+        Mode::Mouse => {
+            println!("Mouse Mode: Translating GCODE axis moves into relative cursor motion.");
+            let drag_mode = if state.mouse_pan { "Pan (Shift+Middle)" } else { "Orbit (Middle)" };
+            println!(
+                "Drag: {} │ Sensitivity: {} px/mm │ Press 'p' to toggle orbit/pan.",
+                drag_mode, state.config.mouse_sensitivity
+            );
+            println!("Press '1' for Arrow Mode, '2' for Gcode Mode, '4' for Mouse Mode, 'q' to quit.");
         }
     }
     println!();
@@ -218,33 +268,87 @@ fn draw_status_bar(state: &AppState) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn serial_to_gcode(line: &str, state: &mut AppState) {
+fn serial_to_gcode(line: &str, state: &mut AppState, injector: &mut dyn KeyInjector) {
     // In Gcode mode, type out received commands as text input
-    type_text(line);
+    injector.type_text(line);
     // Press enter after typing the command
     // Simulate Enter key press
-    send_key_down(0x0D); // VK_RETURN
-    send_key_up(0x0D);  // VK_RETURN
+    injector.key_down(Key::Virtual(0x0D)); // VK_RETURN
+    injector.key_up(Key::Virtual(0x0D)); // VK_RETURN
     state.update_last_command(format!("Typed: {}", line));
 }
 
-/// Virtual key codes for arrow keys and page keys
-const VK_LEFT: u16 = 0x25;
-const VK_UP: u16 = 0x26;
-const VK_RIGHT: u16 = 0x27;
-const VK_DOWN: u16 = 0x28;
-const VK_PAGEUP: u16 = 0x21;
-const VK_PAGEDOWN: u16 = 0x22;
-const VK_CONTROL: u16 = 0x11;
+/// Virtual key code for the Control modifier
+pub(crate) const VK_CONTROL: u16 = 0x11;
+
+/// Virtual key code for the Shift modifier
+pub(crate) const VK_SHIFT: u16 = 0x10;
 
 /// Processes incoming serial data and converts GCODE movement commands to keyboard input
 /// Returns true if a command was processed successfully
-/// Processes incoming serial data and converts GCODE movement commands to keyboard input
-/// Returns true if a command was processed successfully
-fn serial_to_arrow(line: &str, state: &mut AppState) -> bool {
-    // Regex to match G91G0 commands with axis and value: G91G0X10.5, G91G0Y-5, etc.
-    let re = Regex::new(r"G91G0([XYZ])(-?\d+\.?\d*)").unwrap();
+fn serial_to_arrow(line: &str, state: &mut AppState, injector: &mut dyn KeyInjector) -> bool {
+    // Remove "GCODE: " prefix if present
+    let mut command = line.trim();
+    if command.starts_with("GCODE: ") {
+        command = &command[7..];
+    }
 
+    // Update command history
+    state.update_last_command(command.to_string());
+
+    // Try to match and process the command against the configured GCODE pattern
+    let Some(captures) = state.gcode_regex.captures(command) else {
+        return false; // No matching command found
+    };
+
+    // Extract axis and movement value
+    let axis = &captures[1];
+    let value: f32 = match captures[2].parse() {
+        Ok(v) => v,
+        Err(_) => return false,
+    };
+
+    // Look up the configured key chord for this axis and direction
+    let Some(chord) = state.config.axis_bindings.binding_for(axis, value > 0.0) else {
+        return false; // Invalid axis
+    };
+    let chord: Vec<Key> = chord
+        .iter()
+        .map(|&key| scancode::translate_for_mode(key, state.injection_mode, state.scancode_set))
+        .collect();
+
+    // Press the chord down in order, then release in reverse order
+    for &key in &chord {
+        injector.key_down(key);
+    }
+    for &key in chord.iter().rev() {
+        injector.key_up(key);
+    }
+
+    true
+}
+
+/// Releases the Mouse-mode drag button (and its Shift modifier, if the
+/// released drag was panning) if a drag is currently held. No-op if idle.
+fn release_mouse_drag(state: &mut AppState, injector: &mut dyn KeyInjector) {
+    let Some(pan) = state.mouse_drag_active.take() else {
+        return;
+    };
+    injector.button_up(MouseButton::Middle);
+    if pan {
+        injector.key_up(Key::Virtual(VK_SHIFT));
+    }
+}
+
+/// Processes incoming serial data in Mouse mode, translating GCODE X/Y axis
+/// moves into relative cursor motion scaled by `config.mouse_sensitivity`.
+/// The first move of a drag presses the middle button down (or Shift+middle
+/// for pan, per `state.mouse_pan`) and holds it for subsequent moves, so a
+/// stream of incremental jogs reads as one continuous drag rather than a
+/// press+release per line; `run_event_loop` releases the held button once
+/// moves go idle. GCODE has no mouse-wheel equivalent, so Z moves are
+/// ignored. Returns true if a command was processed successfully.
+fn serial_to_mouse(line: &str, state: &mut AppState, injector: &mut dyn KeyInjector) -> bool {
     // Remove "GCODE: " prefix if present
     let mut command = line.trim();
     if command.starts_with("GCODE: ") {
@@ -254,71 +358,179 @@ fn serial_to_arrow(line: &str, state: &mut AppState) -> bool {
     // Update command history
     state.update_last_command(command.to_string());
 
-    // Try to match and process the command
-    if let Some(captures) = re.captures(command) {
-        // Extract axis and movement value
-        let axis = &captures[1];
-        let value: f32 = captures[2].parse().unwrap();
-
-        // Determine which key to press based on axis and direction
-        let key = match axis {
-            "Y" => if value > 0.0 { "up" } else { "down" },
-            "X" => if value > 0.0 { "right" } else { "left" },
-            "Z" => if value > 0.0 { "pageup" } else { "pagedown" },
-            _ => {
-                return false; // Invalid axis
-            }
-        };
+    // Try to match and process the command against the configured GCODE pattern
+    let Some(captures) = state.gcode_regex.captures(command) else {
+        return false; // No matching command found
+    };
 
-        // Map key name to virtual key code
-        let vk = match key {
-            "left" => VK_LEFT,
-            "up" => VK_UP,
-            "right" => VK_RIGHT,
-            "down" => VK_DOWN,
-            "pageup" => VK_PAGEUP,
-            "pagedown" => VK_PAGEDOWN,
-            _ => return false,
-        };
+    // Extract axis and movement value
+    let axis = &captures[1];
+    let value: f32 = match captures[2].parse() {
+        Ok(v) => v,
+        Err(_) => return false,
+    };
 
-        // Simulate Ctrl+key combination (common in CAD software for jogging)
-        send_key_down(VK_CONTROL);
-        send_key_down(vk);
-        send_key_up(vk);
-        send_key_up(VK_CONTROL);
+    let pixels = (value * state.config.mouse_sensitivity).round() as i32;
+    let (dx, dy) = match axis {
+        "X" => (pixels, 0),
+        "Y" => (0, -pixels), // GCODE Y+ moves away from the origin; screen Y grows downward
+        _ => return false, // No mouse-wheel equivalent for Z
+    };
 
-        true
-    } else {
-        false // No matching command found
+    // A drag started under the other modifier must be released before a new
+    // one starts, so toggling pan mid-stream doesn't leave the old button down.
+    if state.mouse_drag_active.is_some_and(|pan| pan != state.mouse_pan) {
+        release_mouse_drag(state, injector);
+    }
+    if state.mouse_drag_active.is_none() {
+        if state.mouse_pan {
+            injector.key_down(Key::Virtual(VK_SHIFT));
+        }
+        injector.button_down(MouseButton::Middle);
+        state.mouse_drag_active = Some(state.mouse_pan);
+    }
+    injector.move_relative(dx, dy);
+    state.mouse_last_move = Some(Instant::now());
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use injector::{HeadlessEvent, HeadlessInjector};
+    use scancode::JogKey;
+
+    fn test_state() -> AppState {
+        AppState::new(Config::default(), PathBuf::from("test.toml")).unwrap()
+    }
+
+    #[test]
+    fn app_state_new_rejects_a_gcode_pattern_with_too_few_capture_groups() {
+        let mut config = Config::default();
+        config.gcode_pattern = r"G91G0([XYZ])".to_string();
+        assert!(AppState::new(config, PathBuf::from("test.toml")).is_err());
+    }
+
+    #[test]
+    fn serial_to_gcode_types_the_line_and_presses_enter() {
+        let mut state = test_state();
+        let mut injector = HeadlessInjector::new();
+
+        serial_to_gcode("G91G0X10", &mut state, &mut injector);
+
+        assert_eq!(
+            injector.events,
+            vec![
+                HeadlessEvent::Text("G91G0X10".to_string()),
+                HeadlessEvent::KeyDown(Key::Virtual(0x0D)),
+                HeadlessEvent::KeyUp(Key::Virtual(0x0D)),
+            ]
+        );
+        assert_eq!(state.last_command, Some("Typed: G91G0X10".to_string()));
+    }
+
+    #[test]
+    fn serial_to_arrow_presses_the_bound_chord_for_a_matching_axis_move() {
+        let mut state = test_state();
+        let mut injector = HeadlessInjector::new();
+
+        assert!(serial_to_arrow("GCODE: G91G0X10", &mut state, &mut injector));
+
+        assert_eq!(
+            injector.events,
+            vec![
+                HeadlessEvent::KeyDown(Key::Virtual(VK_CONTROL)),
+                HeadlessEvent::KeyDown(JogKey::Right.virtual_key()),
+                HeadlessEvent::KeyUp(JogKey::Right.virtual_key()),
+                HeadlessEvent::KeyUp(Key::Virtual(VK_CONTROL)),
+            ]
+        );
+        assert_eq!(state.last_command, Some("G91G0X10".to_string()));
+    }
+
+    #[test]
+    fn serial_to_arrow_presses_the_negative_chord_for_a_negative_move() {
+        let mut state = test_state();
+        let mut injector = HeadlessInjector::new();
+
+        assert!(serial_to_arrow("G91G0Y-5", &mut state, &mut injector));
+
+        assert_eq!(
+            injector.events,
+            vec![
+                HeadlessEvent::KeyDown(Key::Virtual(VK_CONTROL)),
+                HeadlessEvent::KeyDown(JogKey::Down.virtual_key()),
+                HeadlessEvent::KeyUp(JogKey::Down.virtual_key()),
+                HeadlessEvent::KeyUp(Key::Virtual(VK_CONTROL)),
+            ]
+        );
+    }
+
+    #[test]
+    fn serial_to_arrow_returns_false_and_injects_nothing_for_a_non_matching_line() {
+        let mut state = test_state();
+        let mut injector = HeadlessInjector::new();
+
+        assert!(!serial_to_arrow("M114", &mut state, &mut injector));
+        assert!(injector.events.is_empty());
+    }
+
+    #[test]
+    fn serial_to_arrow_uses_scancodes_when_in_scancode_injection_mode() {
+        let mut state = test_state();
+        state.injection_mode = InjectionMode::Scancode;
+        let mut injector = HeadlessInjector::new();
+
+        assert!(serial_to_arrow("G91G0X10", &mut state, &mut injector));
+
+        let expected_key = JogKey::Right.scancode(state.scancode_set);
+        assert_eq!(
+            injector.events,
+            vec![
+                HeadlessEvent::KeyDown(Key::Virtual(VK_CONTROL)),
+                HeadlessEvent::KeyDown(expected_key),
+                HeadlessEvent::KeyUp(expected_key),
+                HeadlessEvent::KeyUp(Key::Virtual(VK_CONTROL)),
+            ]
+        );
     }
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Configuration constants
-    let port_name = "COM6";
-    let baud_rate = 115200;
+    // Load (or create) the user configuration
+    let config_path = PathBuf::from(config::DEFAULT_CONFIG_PATH);
+    let config = Config::load(&config_path)?;
 
     // Attempt to open serial port
-    let port = match serialport::new(port_name, baud_rate)
+    let port = match serialport::new(&config.port, config.baud_rate)
         .timeout(std::time::Duration::from_millis(10))
         .open() {
         Ok(p) => p,
         Err(e) => {
-            eprintln!("Failed to open serial port {}: {}", port_name, e);
+            eprintln!("Failed to open serial port {}: {}", config.port, e);
             return Ok(());
         }
     };
 
-    println!("Serial port {} opened at {} baud rate.", port_name, baud_rate);
+    println!("Serial port {} opened at {} baud rate.", config.port, config.baud_rate);
 
     // Create reader and writer for the serial port
-    let mut reader = io::BufReader::new(port.try_clone()?);
+    let reader = io::BufReader::new(port.try_clone()?);
     let mut writer = port;
 
     // Initialize application state
-    let mut state = AppState::new();
+    let mut state = AppState::new(config, config_path)?;
     state.connected = true;
 
+    // Pick a keyboard injection backend for the current platform
+    let mut injector = make_injector();
+
+    // Hand the blocking serial reads off to a dedicated thread so a burst of
+    // incoming commands can't stall keyboard handling on the main loop.
+    let queue: SharedLineQueue = Arc::new(Mutex::new(LineQueue::new(QUEUE_CAPACITY)));
+    spawn_serial_reader(reader, Arc::clone(&queue));
+
     // Setup terminal for raw mode input handling
     enable_raw_mode()?;
     // execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
@@ -327,7 +539,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     draw_status_bar(&state)?;
 
     // Main event loop
-    run_event_loop(&mut reader, &mut writer, &mut state)?;
+    run_event_loop(&queue, &mut writer, &mut state, injector.as_mut())?;
 
     // Restore terminal settings
     disable_raw_mode()?;
@@ -336,35 +548,104 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Constructs the platform-appropriate [`KeyInjector`], falling back to the headless
+/// backend if no display server is reachable on Unix.
+fn make_injector() -> Box<dyn KeyInjector> {
+    #[cfg(windows)]
+    {
+        Box::new(injector::WindowsInjector::new())
+    }
+    #[cfg(all(unix, not(windows)))]
+    {
+        match injector::XcbInjector::new() {
+            Ok(xcb) => Box::new(xcb),
+            Err(e) => {
+                eprintln!("Failed to connect to X server ({e}), falling back to headless key injection.");
+                Box::new(injector::HeadlessInjector::new())
+            }
+        }
+    }
+    #[cfg(not(any(windows, unix)))]
+    {
+        Box::new(injector::HeadlessInjector::new())
+    }
+}
+
+/// Spawns a dedicated thread that blocks on serial reads and pushes complete
+/// lines onto `queue`, decoupling serial reception from the main loop so a
+/// burst of incoming commands can't stall keyboard handling.
+fn spawn_serial_reader(
+    mut reader: io::BufReader<Box<dyn serialport::SerialPort>>,
+    queue: SharedLineQueue,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || loop {
+        let mut line = String::new();
+        match reader.read_line(&mut line) {
+            Ok(n) if n > 0 => {
+                queue.lock().unwrap().push(line.trim().to_string());
+            }
+            Ok(_) => {} // Port closed with no data; keep polling
+            Err(_) => {} // Read timeout or transient error; keep polling
+        }
+    })
+}
+
 /// Runs the main event loop handling serial data and keyboard input
 fn run_event_loop(
-    reader: &mut io::BufReader<Box<dyn serialport::SerialPort>>,
+    queue: &SharedLineQueue,
     writer: &mut Box<dyn serialport::SerialPort>,
     state: &mut AppState,
+    injector: &mut dyn KeyInjector,
 ) -> Result<(), Box<dyn std::error::Error>> {
     loop {
-        // Check for incoming serial data
-        let mut line = String::new();
-        match reader.read_line(&mut line) {
-            Ok(n) if n > 0 => {
-                let line = line.trim();
+        // Drain every serial line the reader thread has queued so far
+        let mut drained_any = false;
+        loop {
+            let line = {
+                let mut queue = queue.lock().unwrap();
+                state.queue_depth = queue.len();
+                queue.pop()
+            };
+            let Some(line) = line else { break };
+            drained_any = true;
+
+            if state.macro_engine.dispatch(&line, injector) {
+                state.update_last_command(format!("Macro: {}", line));
+            } else {
                 match state.mode {
                     Mode::Arrow => {
-                        serial_to_arrow(line, state);
+                        serial_to_arrow(&line, state, injector);
                     }
                     Mode::Gcode => {
-                        serial_to_gcode(line, state);
+                        serial_to_gcode(&line, state, injector);
+                    }
+                    Mode::Mouse => {
+                        serial_to_mouse(&line, state, injector);
                     }
+                    Mode::Config => {} // Config mode has no mode-specific key injection
                 }
-                draw_status_bar(state)?;
             }
-            _ => {} // No data available or timeout
+        }
+        if drained_any {
+            draw_status_bar(state)?;
+        }
+
+        // Release a held Mouse-mode drag once jog input has gone idle, so the
+        // pendant falling quiet doesn't leave the button stuck down.
+        if state.mouse_drag_active.is_some() {
+            let idle = state
+                .mouse_last_move
+                .map(|t| t.elapsed())
+                .unwrap_or(Duration::MAX);
+            if idle >= MOUSE_DRAG_IDLE_TIMEOUT {
+                release_mouse_drag(state, injector);
+            }
         }
 
         // Check for keyboard input
         if event::poll(Duration::from_millis(10))? {
             if let Event::Key(key) = event::read()? {
-                handle_key_press(key, writer, state)?;
+                handle_key_press(key, writer, state, injector)?;
                 if state.mode == Mode::Gcode {
                     draw_status_bar(state)?;
                 }
@@ -378,16 +659,51 @@ fn handle_key_press(
     key: event::KeyEvent,
     writer: &mut Box<dyn serialport::SerialPort>,
     state: &mut AppState,
+    injector: &mut dyn KeyInjector,
 ) -> Result<(), Box<dyn std::error::Error>> {
     match key.code {
         KeyCode::Char('1') => {
+            release_mouse_drag(state, injector);
             state.mode = Mode::Arrow;
             draw_status_bar(state)?;
         }
         KeyCode::Char('2') => {
+            release_mouse_drag(state, injector);
             state.mode = Mode::Gcode;
             draw_status_bar(state)?;
         }
+        KeyCode::Char('3') => {
+            release_mouse_drag(state, injector);
+            state.mode = Mode::Config;
+            draw_status_bar(state)?;
+        }
+        KeyCode::Char('4') => {
+            state.mode = Mode::Mouse;
+            draw_status_bar(state)?;
+        }
+        KeyCode::Char('r') if matches!(state.mode, Mode::Config) => {
+            state.reload_config()?;
+            draw_status_bar(state)?;
+        }
+        KeyCode::Char('p') if matches!(state.mode, Mode::Mouse) => {
+            release_mouse_drag(state, injector);
+            state.mouse_pan = !state.mouse_pan;
+            draw_status_bar(state)?;
+        }
+        KeyCode::Char('m') if matches!(state.mode, Mode::Arrow) => {
+            state.injection_mode = match state.injection_mode {
+                InjectionMode::Virtual => InjectionMode::Scancode,
+                InjectionMode::Scancode => InjectionMode::Virtual,
+            };
+            draw_status_bar(state)?;
+        }
+        KeyCode::Char('e') if matches!(state.mode, Mode::Arrow) => {
+            state.scancode_set = match state.scancode_set {
+                ScancodeSet::Set1 => ScancodeSet::Set2,
+                ScancodeSet::Set2 => ScancodeSet::Set1,
+            };
+            draw_status_bar(state)?;
+        }
         KeyCode::Char('q') => {
             // Quit the application
             std::process::exit(0);
@@ -395,20 +711,50 @@ fn handle_key_press(
         KeyCode::Enter if matches!(state.mode, Mode::Gcode) => {
             if !state.gcode_input.is_empty() {
                 // Send GCODE command to device
-                let gcode = format!("{}\n", state.gcode_input);
+                let line = state.gcode_input.as_str();
+                let gcode = format!("{}\n", line);
                 writer.write_all(gcode.as_bytes())?;
                 writer.flush()?;
-                state.update_last_command(format!("Sent: {}", state.gcode_input));
+                state.update_last_command(format!("Sent: {}", line));
+                state.gcode_history.push(line);
                 state.gcode_input.clear();
                 draw_status_bar(state)?;
             }
         }
         KeyCode::Backspace if matches!(state.mode, Mode::Gcode) => {
-            state.gcode_input.pop();
+            state.gcode_input.delete_before();
+            draw_status_bar(state)?;
+        }
+        KeyCode::Left if matches!(state.mode, Mode::Gcode) => {
+            state.gcode_input.move_left();
+            draw_status_bar(state)?;
+        }
+        KeyCode::Right if matches!(state.mode, Mode::Gcode) => {
+            state.gcode_input.move_right();
+            draw_status_bar(state)?;
+        }
+        KeyCode::Home if matches!(state.mode, Mode::Gcode) => {
+            state.gcode_input.move_home();
+            draw_status_bar(state)?;
+        }
+        KeyCode::End if matches!(state.mode, Mode::Gcode) => {
+            state.gcode_input.move_end();
+            draw_status_bar(state)?;
+        }
+        KeyCode::Up if matches!(state.mode, Mode::Gcode) => {
+            if let Some(entry) = state.gcode_history.prev() {
+                state.gcode_input.set(entry);
+            }
+            draw_status_bar(state)?;
+        }
+        KeyCode::Down if matches!(state.mode, Mode::Gcode) => {
+            if let Some(entry) = state.gcode_history.next() {
+                state.gcode_input.set(entry);
+            }
             draw_status_bar(state)?;
         }
         KeyCode::Char(c) if matches!(state.mode, Mode::Gcode) => {
-            state.gcode_input.push(c);
+            state.gcode_input.insert(c);
             draw_status_bar(state)?;
         }
         _ => {}
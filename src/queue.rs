@@ -0,0 +1,77 @@
+//! Bounded ring-buffer queue of incoming serial lines, shared between the
+//! dedicated serial-reader thread and the main event loop.
+//!
+//! The reader thread only appends lines as they arrive; the main loop drains
+//! and processes them each tick, so a burst of serial traffic can't starve
+//! keyboard handling. Capacity is fixed so a stalled consumer can't grow
+//! memory unbounded — once full, the oldest queued line is dropped in favor
+//! of the newest, since a stale jog command is worse than a skipped one.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Fixed-capacity queue of serial lines awaiting processing.
+pub struct LineQueue {
+    lines: VecDeque<String>,
+    capacity: usize,
+}
+
+impl LineQueue {
+    /// Creates an empty queue holding at most `capacity` lines.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Appends a line, dropping the oldest queued line first if already at
+    /// capacity.
+    pub fn push(&mut self, line: String) {
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+
+    /// Removes and returns the oldest queued line, if any.
+    pub fn pop(&mut self) -> Option<String> {
+        self.lines.pop_front()
+    }
+
+    /// Number of lines currently queued.
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+}
+
+/// Shared handle to a [`LineQueue`], cloned between the serial-reader thread
+/// and the main loop.
+pub type SharedLineQueue = Arc<Mutex<LineQueue>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pop_returns_lines_in_fifo_order() {
+        let mut queue = LineQueue::new(3);
+        queue.push("a".to_string());
+        queue.push("b".to_string());
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.pop(), Some("a".to_string()));
+        assert_eq!(queue.pop(), Some("b".to_string()));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn push_past_capacity_drops_the_oldest_line() {
+        let mut queue = LineQueue::new(2);
+        queue.push("a".to_string());
+        queue.push("b".to_string());
+        queue.push("c".to_string());
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue.pop(), Some("b".to_string()));
+        assert_eq!(queue.pop(), Some("c".to_string()));
+    }
+}
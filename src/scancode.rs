@@ -0,0 +1,112 @@
+//! Hardware scancode translation for jog keys.
+//!
+//! CAD software and games frequently ignore synthetic virtual-key events but honor
+//! hardware scancodes, so [`serial_to_arrow`](crate::serial_to_arrow) can emit jog
+//! keys as raw scancodes instead of virtual keys. Two translation tables are
+//! supported, mirroring the classic PS/2 keyboard controller's scancode-set switch.
+
+use crate::injector::Key;
+
+/// Whether jog keys are injected as virtual key codes or raw hardware scancodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InjectionMode {
+    /// Emit jog keys as platform virtual key codes (the original behavior).
+    Virtual,
+    /// Emit jog keys as raw hardware scancodes, per `scancode_set`.
+    Scancode,
+}
+
+/// The scancode set used to translate jog keys when `InjectionMode::Scancode` is
+/// active, mirroring the PS/2 keyboard controller's Set 1 (XT/AT, the default)
+/// and Set 2 switch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScancodeSet {
+    /// The default XT/AT scancode set.
+    Set1,
+    /// The alternate scancode set used internally by most PS/2 keyboards.
+    Set2,
+}
+
+/// The jog directions `serial_to_arrow` maps incoming GCODE moves to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JogKey {
+    Left,
+    Up,
+    Right,
+    Down,
+    PageUp,
+    PageDown,
+}
+
+impl JogKey {
+    /// Looks up the virtual key code for this jog key.
+    pub fn virtual_key(self) -> Key {
+        let vk = match self {
+            JogKey::Left => 0x25,
+            JogKey::Up => 0x26,
+            JogKey::Right => 0x27,
+            JogKey::Down => 0x28,
+            JogKey::PageUp => 0x21,
+            JogKey::PageDown => 0x22,
+        };
+        Key::Virtual(vk)
+    }
+
+    /// Looks up the hardware scancode for this jog key in the given scancode set.
+    /// Jog keys are all "extended" (0xE0-prefixed) keys in both sets.
+    pub fn scancode(self, set: ScancodeSet) -> Key {
+        let code = match (self, set) {
+            (JogKey::Left, ScancodeSet::Set1) => 0x4B,
+            (JogKey::Up, ScancodeSet::Set1) => 0x48,
+            (JogKey::Right, ScancodeSet::Set1) => 0x4D,
+            (JogKey::Down, ScancodeSet::Set1) => 0x50,
+            (JogKey::PageUp, ScancodeSet::Set1) => 0x49,
+            (JogKey::PageDown, ScancodeSet::Set1) => 0x51,
+            (JogKey::Left, ScancodeSet::Set2) => 0x6B,
+            (JogKey::Up, ScancodeSet::Set2) => 0x75,
+            (JogKey::Right, ScancodeSet::Set2) => 0x74,
+            (JogKey::Down, ScancodeSet::Set2) => 0x72,
+            (JogKey::PageUp, ScancodeSet::Set2) => 0x7D,
+            (JogKey::PageDown, ScancodeSet::Set2) => 0x7A,
+        };
+        Key::Scan {
+            code,
+            extended: true,
+        }
+    }
+
+    /// Looks up the key to inject for this jog key under the given mode/set.
+    pub fn key(self, mode: InjectionMode, set: ScancodeSet) -> Key {
+        match mode {
+            InjectionMode::Virtual => self.virtual_key(),
+            InjectionMode::Scancode => self.scancode(set),
+        }
+    }
+
+    /// Reverse lookup: which jog key (if any) a virtual key code corresponds to.
+    fn from_virtual(vk: u16) -> Option<JogKey> {
+        match vk {
+            0x25 => Some(JogKey::Left),
+            0x26 => Some(JogKey::Up),
+            0x27 => Some(JogKey::Right),
+            0x28 => Some(JogKey::Down),
+            0x21 => Some(JogKey::PageUp),
+            0x22 => Some(JogKey::PageDown),
+            _ => None,
+        }
+    }
+}
+
+/// Translates a key bound in a (possibly user-configured) chord to the current
+/// injection mode. Non-jog keys (e.g. a Ctrl modifier) and raw scancodes already
+/// pass through unchanged; only virtual jog keys are swapped for their scancode
+/// equivalent when `mode` is `Scancode`.
+pub fn translate_for_mode(key: Key, mode: InjectionMode, set: ScancodeSet) -> Key {
+    match key {
+        Key::Virtual(vk) => match JogKey::from_virtual(vk) {
+            Some(jog) => jog.key(mode, set),
+            None => key,
+        },
+        Key::Scan { .. } => key,
+    }
+}